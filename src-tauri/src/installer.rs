@@ -36,6 +36,30 @@ fn exec_admin<E: AsRef<Path>, A: AsRef<OsStr>>(executable: E, args: &[A]) -> Res
     Ok(())
 }
 
+/// Path to the directory projects are laid out under, or `None` if the home
+/// directory cannot be resolved. The directory itself may not exist yet.
+pub fn projects_path() -> Option<PathBuf> {
+    Some(
+        home_dir()?
+            .join("Library")
+            .join("GennakerTauri")
+            .join("projects"),
+    )
+}
+
+/// Path to the packed resource blob serving the base Python standard
+/// library and the shared `jupyter-libraries` modules, or `None` if the home
+/// directory cannot be resolved. The file itself may not exist yet.
+pub fn libraries_blob_path() -> Option<PathBuf> {
+    Some(
+        home_dir()?
+            .join("Library")
+            .join("GennakerTauri")
+            .join("jupyter-libraries")
+            .join("jupyter-libraries.gpyres"),
+    )
+}
+
 pub fn install_if_needed(path_resolver: PathResolver) -> Result<()> {
     if let Some(home) = home_dir() {
         let gennaker_path = home.join("Library").join("GennakerTauri");
@@ -87,11 +111,17 @@ pub fn install_if_needed(path_resolver: PathResolver) -> Result<()> {
             )?;
         }
 
+        // the base interpreter's pure-Python standard library and the shared
+        // `jupyter-libraries` modules are immutable, so they're served from a
+        // single packed resource blob (see `packed_resources`) instead of
+        // being copied out file-by-file
         if !libraries_exist {
-            fs_extra::copy_items(
-                &[path_resolver.resolve_resource("jupyter-libraries").unwrap()],
-                &gennaker_path,
-                &options,
+            create_dir_all(&libraries_path)?;
+            std::fs::copy(
+                path_resolver
+                    .resolve_resource("jupyter-libraries.gpyres")
+                    .expect("failed to resolve jupyter-libraries.gpyres"),
+                libraries_path.join("jupyter-libraries.gpyres"),
             )?;
         }
 
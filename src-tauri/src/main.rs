@@ -21,15 +21,26 @@ use tauri::{
 };
 
 mod installer;
+mod packed_resources;
+mod python_env;
+mod tunnel;
+
+#[cfg(target_os = "linux")]
+use crate::python_env::PythonEnvCommand;
 
 struct JupyterProcess {
     child: CommandChild,
     port: u16,
     token: String,
+    tunnel: Option<tunnel::TunnelHandle>,
 }
 
 impl JupyterProcess {
     pub async fn stop(self) -> Result<()> {
+        if let Some(tunnel) = self.tunnel {
+            tunnel.stop().await;
+        }
+
         #[cfg(not(windows))]
         {
             let client = ClientBuilder::new().build()?;
@@ -65,6 +76,20 @@ async fn launch(
         .map_err(|e| e.to_string())
 }
 
+/// Classifies a line emitted by the Jupyter subprocess and logs it at the
+/// matching level: a traceback or error message logs as `error`, a warning
+/// logs as `warn`, and the `is running at` handshake plus everything else
+/// logs as `info`.
+fn log_jupyter_line(message: &str) {
+    if message.contains("Traceback") || message.contains("Error") {
+        log::error!("{message}");
+    } else if message.contains("Warning") {
+        log::warn!("{message}");
+    } else {
+        log::info!("{message}");
+    }
+}
+
 async fn do_launch(
     app: AppHandle,
     window: Window,
@@ -85,6 +110,56 @@ async fn do_launch(
             "GPYTHON_FRAMEWORK_PATH".to_string(),
             gpython_framework_path()?.to_string_lossy().to_string(),
         );
+
+        // launch.sh execs `python` directly, so the sandbox-specific library
+        // and module search paths a Flatpak/Snap/AppImage wrapper injects
+        // must be stripped from its environment here, before it is spawned
+        #[cfg(target_os = "linux")]
+        for (var_name, value) in crate::python_env::normalized_sandbox_env() {
+            env.insert(var_name, value.to_string_lossy().to_string());
+        }
+
+        // if the base stdlib and `jupyter-libraries` were installed as a
+        // packed resource blob, drop a `sitecustomize.py` next to it that
+        // registers the `sys.meta_path` importer, and prepend its directory
+        // to `PYTHONPATH`: the `site` module auto-imports `sitecustomize`
+        // from every `PYTHONPATH` entry at interpreter startup, so the
+        // importer is registered before `python -m jupyterlab` runs without
+        // launch.sh needing to know anything about it
+        if let Some(blob_path) = installer::libraries_blob_path().filter(|path| path.exists()) {
+            let bootstrap_dir = blob_path
+                .parent()
+                .ok_or_else(|| anyhow::anyhow!("packed resource blob has no parent directory"))?;
+            std::fs::write(
+                bootstrap_dir.join("sitecustomize.py"),
+                packed_resources::bootstrap_script(&blob_path),
+            )?;
+
+            // inside a sandbox the map holds PYTHONPATH with its sandbox
+            // entries already stripped; otherwise the inherited value is kept
+            #[cfg(target_os = "linux")]
+            let sandboxed = crate::python_env::is_flatpak()
+                || crate::python_env::is_snap()
+                || crate::python_env::is_appimage();
+            #[cfg(not(target_os = "linux"))]
+            let sandboxed = false;
+            let existing = match env.get("PYTHONPATH") {
+                Some(value) => Some(std::ffi::OsString::from(value)),
+                None if !sandboxed => std::env::var_os("PYTHONPATH"),
+                None => None,
+            };
+            let mut python_path_entries = vec![bootstrap_dir.to_path_buf()];
+            if let Some(existing) = existing {
+                python_path_entries.extend(std::env::split_paths(&existing));
+            }
+            env.insert(
+                "PYTHONPATH".to_string(),
+                std::env::join_paths(python_path_entries)?
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+
         let (mut rx, child) = Command::new(
             app.path_resolver()
                 .resolve_resource("launch.sh")
@@ -100,11 +175,8 @@ async fn do_launch(
 
         while let Some(event) = rx.recv().await {
             match &event {
-                CommandEvent::Stderr(message) => {
-                    eprintln!("{message}");
-                }
-                CommandEvent::Stdout(message) => {
-                    println!("{message}");
+                CommandEvent::Stderr(message) | CommandEvent::Stdout(message) => {
+                    log_jupyter_line(message);
                 }
                 CommandEvent::Error(e) => {
                     anyhow::bail!("failed to run launcher: {e}")
@@ -139,11 +211,15 @@ async fn do_launch(
         .title("JupyterLab")
         .build();
 
-        store
-            .0
-            .lock()
-            .unwrap()
-            .insert(child.pid(), JupyterProcess { child, port, token });
+        store.0.lock().unwrap().insert(
+            child.pid(),
+            JupyterProcess {
+                child,
+                port,
+                token,
+                tunnel: None,
+            },
+        );
 
         Ok(())
     } else {
@@ -151,6 +227,171 @@ async fn do_launch(
     }
 }
 
+/// Opens a relay tunnel to the Jupyter server running under `pid`, returning
+/// the public URL (carrying the existing session token) that another device
+/// can open to reach it.
+#[tauri::command]
+async fn start_tunnel(
+    store: State<'_, JupyterProcessStore>,
+    pid: u32,
+    relay_host: String,
+    registration_code: String,
+) -> Result<String, String> {
+    let (port, token) = {
+        let store_ = store.0.lock().unwrap();
+        let process = store_
+            .get(&pid)
+            .ok_or_else(|| "no running Jupyter process for this pid".to_string())?;
+        (process.port, process.token.clone())
+    };
+
+    // shut down the tunnel a previous call opened for this pid before its
+    // replacement is dialed
+    let existing = store
+        .0
+        .lock()
+        .unwrap()
+        .get_mut(&pid)
+        .and_then(|process| process.tunnel.take());
+    if let Some(existing) = existing {
+        existing.stop().await;
+    }
+
+    let handle = tunnel::start_tunnel(&relay_host, &registration_code, port, &token)
+        .await
+        .map_err(|e| e.to_string())?;
+    let public_url = handle.public_url.clone();
+
+    if let Some(process) = store.0.lock().unwrap().get_mut(&pid) {
+        process.tunnel = Some(handle);
+    }
+
+    Ok(public_url)
+}
+
+#[tauri::command]
+async fn stop_tunnel(store: State<'_, JupyterProcessStore>, pid: u32) -> Result<(), String> {
+    let handle = store
+        .0
+        .lock()
+        .unwrap()
+        .get_mut(&pid)
+        .and_then(|process| process.tunnel.take());
+
+    if let Some(handle) = handle {
+        handle.stop().await;
+    }
+
+    Ok(())
+}
+
+/// Reveals the current log file in the system file manager so users can
+/// attach it to bug reports.
+#[tauri::command]
+fn open_logs(app: AppHandle) -> Result<(), String> {
+    // matches the default file name `tauri_plugin_log`'s `LogDir` target
+    // writes to; with `RotationStrategy::KeepAll` older days are rotated
+    // aside under a timestamped name, so this is always the active one
+    let log_dir = app
+        .path_resolver()
+        .app_log_dir()
+        .ok_or_else(|| "failed to resolve log directory".to_string())?;
+    let log_file = log_dir.join(format!("{}.log", app.package_info().name));
+
+    reveal_path(&log_file)
+}
+
+/// Resolves `path`, rejecting it unless it lives inside the managed
+/// projects directory (guarding against traversal out of it).
+fn resolve_managed_path(path: PathBuf) -> Result<PathBuf, String> {
+    let projects_path =
+        installer::projects_path().ok_or_else(|| "failed to resolve projects directory".to_string())?;
+    let projects_path = projects_path
+        .canonicalize()
+        .map_err(|e| format!("failed to resolve projects directory: {e}"))?;
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| format!("failed to resolve {}: {e}", path.display()))?;
+
+    if !resolved.starts_with(&projects_path) {
+        return Err(format!("{} is outside the managed projects directory", path.display()));
+    }
+
+    Ok(resolved)
+}
+
+/// Reveals `path` in the platform file manager, highlighting it. Shared by
+/// [`reveal_in_file_manager`] (which first validates the path is inside the
+/// managed projects directory) and [`open_logs`] (which points it at the
+/// active log file instead).
+fn reveal_path(path: &Path) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "windows")]
+    std::process::Command::new("explorer")
+        .arg(format!("/select,{}", path.display()))
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut cmd = std::process::Command::new("dbus-send");
+        cmd.args([
+            "--session",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &format!("array:string:file://{}", path.display()),
+            "string:",
+        ]);
+        cmd.normalize_environment();
+        cmd.spawn().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Reveals `path` in the platform file manager, highlighting it.
+#[tauri::command]
+fn reveal_in_file_manager(path: PathBuf) -> Result<(), String> {
+    let resolved = resolve_managed_path(path)?;
+    reveal_path(&resolved)
+}
+
+/// Opens `path` with the platform's default application for its file type.
+#[tauri::command]
+fn open_with_default(path: PathBuf) -> Result<(), String> {
+    let resolved = resolve_managed_path(path)?;
+
+    #[cfg(target_os = "macos")]
+    std::process::Command::new("open")
+        .arg(&resolved)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "windows")]
+    std::process::Command::new("explorer")
+        .arg(&resolved)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut cmd = std::process::Command::new("gio");
+        cmd.arg("open").arg(&resolved);
+        cmd.normalize_environment();
+        cmd.spawn().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 fn gpython_framework_path() -> Result<PathBuf> {
     if cfg!(dev) {
         Ok(PathBuf::from(concat!(
@@ -186,8 +427,30 @@ fn main() {
     // The `fix-path-env` crate fixes the PATH variable
     let _ = fix_path_env::fix();
 
+    // in a `windows_subsystem = "windows"` release build there is no console
+    // attached, so the rotating log file is the only place a failed launch
+    // can be diagnosed from
+    let mut log_targets = vec![tauri_plugin_log::LogTarget::LogDir];
+    if cfg!(debug_assertions) {
+        log_targets.push(tauri_plugin_log::LogTarget::Stdout);
+    }
+
     tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![launch])
+        .plugin(
+            tauri_plugin_log::Builder::default()
+                .targets(log_targets)
+                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
+                .level(log::LevelFilter::Info)
+                .build(),
+        )
+        .invoke_handler(tauri::generate_handler![
+            launch,
+            open_logs,
+            start_tunnel,
+            stop_tunnel,
+            reveal_in_file_manager,
+            open_with_default
+        ])
         .manage(JupyterProcessStore(Default::default()))
         .setup(|app| {
             WindowBuilder::new(app, "init", Default::default())
@@ -0,0 +1,166 @@
+//! Writes the packed Python resource blob used to serve the base standard
+//! library and the shared `jupyter-libraries` modules from a single file
+//! instead of a loose, recursively-copied directory tree.
+//!
+//! Blob layout: an 8 byte magic, a little-endian `u32` version, a
+//! little-endian `u32` resource count, then that many [`Entry`] records
+//! (name length, name, flags, data offset, data length, all little-endian),
+//! followed by the contiguous data section the entries point into. Entries
+//! are kept sorted by name so lookups can binary search the index instead of
+//! scanning it.
+//!
+//! [`pack`] is the only producer of this format and [`bootstrap_script`]'s
+//! embedded Python loader is the only consumer; there is deliberately no
+//! Rust-side reader so the layout has a single source of truth instead of
+//! two parsers that can drift apart.
+
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"GPYRES01";
+const VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceFlags {
+    pub is_source: bool,
+    pub is_bytecode: bool,
+    pub is_package: bool,
+}
+
+impl ResourceFlags {
+    fn to_bits(self) -> u8 {
+        self.is_source as u8 | (self.is_bytecode as u8) << 1 | (self.is_package as u8) << 2
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            is_source: bits & 0b001 != 0,
+            is_bytecode: bits & 0b010 != 0,
+            is_package: bits & 0b100 != 0,
+        }
+    }
+}
+
+struct Entry {
+    name: String,
+    flags: ResourceFlags,
+    data_offset: u64,
+    data_length: u64,
+}
+
+/// Packs `resources` (module name, contents, flags) into a blob, sorting the
+/// index by name so the runtime importer can binary search it.
+pub fn pack(resources: &[(String, Vec<u8>, ResourceFlags)]) -> Vec<u8> {
+    let mut resources = resources.to_vec();
+    resources.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    let mut index = Vec::with_capacity(resources.len());
+    let mut data = Vec::new();
+    for (name, bytes, flags) in &resources {
+        index.push(Entry {
+            name: name.clone(),
+            flags: *flags,
+            data_offset: data.len() as u64,
+            data_length: bytes.len() as u64,
+        });
+        data.extend_from_slice(bytes);
+    }
+
+    let mut blob = Vec::new();
+    blob.extend_from_slice(MAGIC);
+    blob.extend_from_slice(&VERSION.to_le_bytes());
+    blob.extend_from_slice(&(index.len() as u32).to_le_bytes());
+    for entry in &index {
+        let name_bytes = entry.name.as_bytes();
+        blob.extend_from_slice(&(name_bytes.len() as u16).to_le_bytes());
+        blob.extend_from_slice(name_bytes);
+        blob.push(entry.flags.to_bits());
+        blob.extend_from_slice(&entry.data_offset.to_le_bytes());
+        blob.extend_from_slice(&entry.data_length.to_le_bytes());
+    }
+    blob.extend_from_slice(&data);
+    blob
+}
+
+/// Builds the Python source for a `sys.meta_path` finder/loader that
+/// resolves `find_spec`/`get_source`/`get_code` against `blob_path` by
+/// parsing the same header/index layout [`pack`] writes and binary
+/// searching it. The caller writes this out as a `sitecustomize.py` next to
+/// the blob and prepends that directory to `PYTHONPATH`, so the `site`
+/// module imports it and registers the importer automatically at
+/// interpreter startup.
+pub fn bootstrap_script(blob_path: &Path) -> String {
+    format!(
+        r#"import bisect, marshal, struct, sys, importlib.abc, importlib.util, mmap as _mmap
+
+class _PackedResourceLoader(importlib.abc.InspectLoader):
+    _MAGIC = b"GPYRES01"
+
+    def __init__(self, blob_path):
+        self._file = open(blob_path, "rb")
+        self._mmap = _mmap.mmap(self._file.fileno(), 0, access=_mmap.ACCESS_READ)
+        if self._mmap[:8] != self._MAGIC:
+            raise ImportError(f"{{blob_path}} is not a valid packed resource blob")
+        version, count = struct.unpack_from("<II", self._mmap, 8)
+        if version != 1:
+            raise ImportError(f"unsupported packed resource blob version {{version}}")
+
+        offset = 16
+        self._index = []
+        for _ in range(count):
+            (name_len,) = struct.unpack_from("<H", self._mmap, offset)
+            offset += 2
+            name = self._mmap[offset:offset + name_len].decode("utf-8")
+            offset += name_len
+            (flags,) = struct.unpack_from("<B", self._mmap, offset)
+            offset += 1
+            data_offset, data_length = struct.unpack_from("<QQ", self._mmap, offset)
+            offset += 16
+            self._index.append((name, flags, data_offset, data_length))
+        self._data_start = offset
+        self._names = [entry[0] for entry in self._index]
+
+    def _find(self, fullname):
+        i = bisect.bisect_left(self._names, fullname)
+        if i < len(self._names) and self._names[i] == fullname:
+            return self._index[i]
+        return None
+
+    def find_spec(self, fullname, path, target=None):
+        entry = self._find(fullname)
+        if entry is None:
+            return None
+        _, flags, _, _ = entry
+        return importlib.util.spec_from_loader(fullname, self, is_package=bool(flags & 0b100))
+
+    def _read(self, entry):
+        _, _, data_offset, data_length = entry
+        start = self._data_start + data_offset
+        return bytes(self._mmap[start:start + data_length])
+
+    def get_source(self, fullname):
+        entry = self._find(fullname)
+        if entry is None or not (entry[1] & 0b001):
+            return None
+        return self._read(entry).decode("utf-8")
+
+    def get_code(self, fullname):
+        entry = self._find(fullname)
+        if entry is None:
+            return None
+        _, flags, _, _ = entry
+        # bytecode-only resources (no source shipped) are unmarshalled
+        # directly instead of going through get_source()/compile()
+        if flags & 0b010:
+            return marshal.loads(self._read(entry))
+        # entries with neither flag set are bare package markers (namespace
+        # packages have no source of their own); compile an empty module
+        # rather than returning None, which InspectLoader.exec_module()
+        # turns into an ImportError
+        source = self.get_source(fullname) or ""
+        return compile(source, fullname, "exec")
+
+sys.meta_path.insert(0, _PackedResourceLoader({blob_path:?}))
+"#,
+        blob_path = blob_path.display().to_string(),
+    )
+}
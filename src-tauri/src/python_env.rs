@@ -1,6 +1,13 @@
-use std::{ffi::OsString, path::Path, process::Command};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
 
-fn path_with_python_env(env_path: &Path) -> crate::Result<OsString> {
+use anyhow::Result;
+
+fn path_with_python_env(env_path: &Path) -> Result<OsString> {
     let mut paths = if let Some(path) = std::env::var_os("PATH") {
         std::env::split_paths(&path).collect::<Vec<_>>()
     } else {
@@ -29,6 +36,12 @@ fn path_with_python_env(env_path: &Path) -> crate::Result<OsString> {
 pub trait PythonEnvCommand {
     /// Adds the given Python environment path to the `PATH`.
     fn add_env_to_path(&mut self, env_path: &Path) -> &mut Self;
+
+    /// Strips the sandbox-specific library and module search paths that
+    /// Flatpak/Snap/AppImage wrappers inject, so the spawned `python`
+    /// process resolves modules the same way it would outside the sandbox.
+    #[cfg(target_os = "linux")]
+    fn normalize_environment(&mut self) -> &mut Self;
 }
 
 impl PythonEnvCommand for Command {
@@ -38,4 +51,136 @@ impl PythonEnvCommand for Command {
         }
         self
     }
+
+    #[cfg(target_os = "linux")]
+    fn normalize_environment(&mut self) -> &mut Self {
+        if !(is_flatpak() || is_snap() || is_appimage()) {
+            return self;
+        }
+
+        for var_name in SANDBOX_PATHLIST_VARS {
+            match normalize_pathlist(var_name, &[]) {
+                Some(value) => {
+                    self.env(var_name, value);
+                }
+                None => {
+                    self.env_remove(var_name);
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// `PATH`-style variables that sandbox wrappers (Flatpak, Snap, AppImage)
+/// point at their own runtime, and that therefore corrupt module resolution
+/// for the spawned `python -m jupyterlab` process if left untouched.
+#[cfg(target_os = "linux")]
+const SANDBOX_PATHLIST_VARS: &[&str] = &[
+    "LD_LIBRARY_PATH",
+    "GST_PLUGIN_PATH",
+    "PYTHONPATH",
+    "XDG_DATA_DIRS",
+];
+
+/// Root directory of the sandbox runtime a wrapper injected its own paths
+/// from, e.g. Flatpak's `/app`, the AppImage mount under `$APPDIR`, or the
+/// Snap's read-only `$SNAP` tree. This is *not* the directory the app's own
+/// executable lives in: Flatpak's `LD_LIBRARY_PATH`/`GST_PLUGIN_PATH` point
+/// at `/app/lib*` and AppImage's point at `$APPDIR/usr/lib*`, both of which
+/// are siblings of the binary, not children of it.
+#[cfg(target_os = "linux")]
+fn sandbox_root() -> Option<PathBuf> {
+    if let Some(appdir) = std::env::var_os("APPDIR") {
+        return Some(PathBuf::from(appdir));
+    }
+    if let Some(snap) = std::env::var_os("SNAP") {
+        return Some(PathBuf::from(snap));
+    }
+    if is_flatpak() {
+        return Some(PathBuf::from("/app"));
+    }
+    None
+}
+
+/// Splits a `PATH`-style environment variable, drops entries pointing inside
+/// the sandbox runtime (see [`sandbox_root`]), removes empty segments and
+/// de-duplicates while keeping the *last* (lowest-priority) occurrence of
+/// any repeated directory. Returns `None` if nothing is left to set, so
+/// callers never end up setting the variable to an empty string.
+#[cfg(target_os = "linux")]
+fn normalize_pathlist(var_name: &str, prepend_entries: &[PathBuf]) -> Option<OsString> {
+    let sandbox_root = sandbox_root();
+
+    let existing = std::env::var_os(var_name)
+        .map(|value| std::env::split_paths(&value).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut seen = HashSet::new();
+    let mut deduped = Vec::new();
+    for entry in existing.into_iter().rev() {
+        if entry.as_os_str().is_empty() {
+            continue;
+        }
+        if let Some(sandbox_root) = &sandbox_root {
+            if entry.starts_with(sandbox_root) {
+                continue;
+            }
+        }
+        if seen.insert(entry.clone()) {
+            deduped.push(entry);
+        }
+    }
+    deduped.reverse();
+
+    let mut paths = prepend_entries.to_vec();
+    paths.append(&mut deduped);
+
+    if paths.is_empty() {
+        return None;
+    }
+
+    std::env::join_paths(paths).ok()
+}
+
+/// Whether the app is running inside a Flatpak sandbox.
+#[cfg(target_os = "linux")]
+pub fn is_flatpak() -> bool {
+    Path::new("/.flatpak-info").exists()
+}
+
+/// Whether the app is running inside a Snap confinement.
+#[cfg(target_os = "linux")]
+pub fn is_snap() -> bool {
+    std::env::var_os("SNAP").is_some()
+}
+
+/// Whether the app is running as a mounted AppImage.
+#[cfg(target_os = "linux")]
+pub fn is_appimage() -> bool {
+    std::env::var_os("APPIMAGE").is_some() || std::env::var_os("APPDIR").is_some()
+}
+
+/// Computes sandbox-stripped replacement values for [`SANDBOX_PATHLIST_VARS`],
+/// keyed by variable name, for callers that build up an environment map
+/// themselves (e.g. before spawning through `tauri::api::process::Command`)
+/// rather than mutating a `std::process::Command` directly. Outside a
+/// sandbox the map is empty and the inherited values stand.
+/// Inside one, a variable absent from the map had no entries left and should
+/// be left unset rather than set to an empty string.
+#[cfg(target_os = "linux")]
+pub fn normalized_sandbox_env() -> HashMap<String, OsString> {
+    let mut env = HashMap::new();
+    if !(is_flatpak() || is_snap() || is_appimage()) {
+        return env;
+    }
+
+    for var_name in SANDBOX_PATHLIST_VARS {
+        if let Some(value) = normalize_pathlist(var_name, &[]) {
+            env.insert((*var_name).to_string(), value);
+        }
+    }
+
+    env
 }
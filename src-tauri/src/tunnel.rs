@@ -0,0 +1,186 @@
+//! Outbound relay tunnel so a notebook running on this machine can be
+//! reached from another device without manual SSH port forwarding: the app
+//! dials out to a relay host, authenticates once with a registration code,
+//! and keeps the connection open, relaying frames between the relay and the
+//! local Jupyter server.
+//!
+//! A browser opening the JupyterLab UI makes several concurrent HTTP
+//! connections plus separate long-lived WebSocket connections per kernel, so
+//! frames are multiplexed over the single relay connection: each
+//! `Message::Binary` is a 4 byte little-endian connection id followed by the
+//! payload for that connection, with an empty payload signalling that
+//! connection's close. A fresh local `TcpStream` is dialed per id the relay
+//! introduces, so concurrent connections never get interleaved onto the same
+//! socket.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use futures_util::{SinkExt, StreamExt};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::{mpsc, oneshot},
+};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+/// A live tunnel connection. Dropping this has the same effect as calling
+/// [`TunnelHandle::stop`]: the relay task sees its shutdown channel close and
+/// exits, so callers store the handle for as long as the tunnel should stay up.
+pub struct TunnelHandle {
+    pub public_url: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl TunnelHandle {
+    pub async fn stop(self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+fn frame(connection_id: u32, payload: &[u8]) -> Message {
+    let mut bytes = connection_id.to_le_bytes().to_vec();
+    bytes.extend_from_slice(payload);
+    Message::Binary(bytes)
+}
+
+/// Dials `relay_host`, authenticates with `registration_code`, and spawns a
+/// background task that demultiplexes inbound HTTP/WebSocket frames onto a
+/// fresh local connection per connection id, forwarding to the Jupyter
+/// server listening on `local_port` for as long as the returned
+/// [`TunnelHandle`] lives.
+pub async fn start_tunnel(
+    relay_host: &str,
+    registration_code: &str,
+    local_port: u16,
+    token: &str,
+) -> Result<TunnelHandle> {
+    let (relay_ws, _) = connect_async(format!("wss://{relay_host}/connect"))
+        .await
+        .with_context(|| format!("failed to dial relay host {relay_host}"))?;
+    let (mut relay_write, mut relay_read) = relay_ws.split();
+
+    relay_write
+        .send(Message::Text(registration_code.to_string()))
+        .await
+        .context("failed to authenticate with relay")?;
+
+    // the relay's first message back is the public host it registered for us
+    let public_host = match relay_read.next().await {
+        Some(Ok(Message::Text(host))) => host,
+        Some(Ok(other)) => bail!("relay sent an unexpected registration reply: {other}"),
+        Some(Err(e)) => return Err(e).context("relay connection closed during registration"),
+        None => bail!("relay closed the connection during registration"),
+    };
+    let public_url = format!("https://{public_host}/lab?token={token}");
+
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    // every per-connection task forwards local reads through this single
+    // channel so they can all write back to the relay without fighting over
+    // `relay_write`
+    let (to_relay_tx, mut to_relay_rx) = mpsc::unbounded_channel::<Message>();
+
+    tauri::async_runtime::spawn(async move {
+        while let Some(message) = to_relay_rx.recv().await {
+            if relay_write.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut connections: HashMap<u32, mpsc::UnboundedSender<Vec<u8>>> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                frame = relay_read.next() => {
+                    match frame {
+                        Some(Ok(Message::Binary(bytes))) if bytes.len() >= 4 => {
+                            let connection_id = u32::from_le_bytes(bytes[..4].try_into().unwrap());
+                            let payload = &bytes[4..];
+
+                            if payload.is_empty() {
+                                connections.remove(&connection_id);
+                                continue;
+                            }
+
+                            let sender = connections.entry(connection_id).or_insert_with(|| {
+                                spawn_local_connection(connection_id, local_port, to_relay_tx.clone())
+                            });
+
+                            if sender.send(payload.to_vec()).is_err() {
+                                connections.remove(&connection_id);
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) | None => break,
+                        Some(Err(e)) => {
+                            log::error!("tunnel: relay connection error: {e}");
+                            break;
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(TunnelHandle {
+        public_url,
+        shutdown: shutdown_tx,
+    })
+}
+
+/// Dials a fresh local connection for `connection_id` and wires it up to
+/// forward in both directions: bytes sent on the returned channel are
+/// written to the local socket, and bytes read from it are framed with
+/// `connection_id` and pushed onto `to_relay`.
+fn spawn_local_connection(
+    connection_id: u32,
+    local_port: u16,
+    to_relay: mpsc::UnboundedSender<Message>,
+) -> mpsc::UnboundedSender<Vec<u8>> {
+    let (to_local_tx, mut to_local_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+
+    tauri::async_runtime::spawn(async move {
+        let stream = match TcpStream::connect(("127.0.0.1", local_port)).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::error!("tunnel[{connection_id}]: failed to reach local Jupyter server: {e}");
+                return;
+            }
+        };
+        let (mut local_read, mut local_write) = stream.into_split();
+
+        let reader_to_relay = to_relay.clone();
+        let reader = tauri::async_runtime::spawn(async move {
+            let mut buf = [0u8; 8192];
+            loop {
+                match local_read.read(&mut buf).await {
+                    Ok(0) => break,
+                    Ok(n) => {
+                        if reader_to_relay.send(frame(connection_id, &buf[..n])).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("tunnel[{connection_id}]: local connection error: {e}");
+                        break;
+                    }
+                }
+            }
+            // tell the relay this connection is done
+            let _ = reader_to_relay.send(frame(connection_id, &[]));
+        });
+
+        while let Some(payload) = to_local_rx.recv().await {
+            if local_write.write_all(&payload).await.is_err() {
+                break;
+            }
+        }
+
+        reader.abort();
+    });
+
+    to_local_tx
+}